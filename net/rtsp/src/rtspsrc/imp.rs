@@ -11,13 +11,13 @@
 //
 // https://www.rfc-editor.org/rfc/rfc2326.html
 
-use std::collections::{btree_set::BTreeSet, HashMap};
+use std::collections::{btree_set::BTreeSet, HashMap, VecDeque};
 use std::convert::TryFrom;
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::num::NonZeroUsize;
 use std::pin::Pin;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use anyhow::Result;
@@ -27,14 +27,14 @@ use futures::{Sink, SinkExt, Stream, StreamExt};
 use socket2::Socket;
 use tokio::net::{TcpStream, UdpSocket};
 use tokio::runtime;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::task::JoinHandle;
 use tokio::time;
 
 use rtsp_types::headers::{
     CSeq, NptRange, NptTime, Public, Range, RtpInfos, RtpLowerTransport, RtpProfile, RtpTransport,
-    RtpTransportParameters, Session, Transport, TransportMode, Transports, ACCEPT, CONTENT_BASE,
-    CONTENT_LOCATION, USER_AGENT,
+    RtpTransportParameters, Session, SmpteRange, SmpteTime, SmpteType, Transport, TransportMode,
+    Transports, UtcRange, UtcTime, ACCEPT, CONTENT_BASE, CONTENT_LOCATION, RANGE, USER_AGENT,
 };
 use rtsp_types::{Message, Method, Request, Response, StatusCode, Version};
 
@@ -50,6 +50,7 @@ use gst_net::gio;
 use super::body::Body;
 use super::sdp;
 use super::transport::RtspTransportInfo;
+use super::SeekFormat;
 
 const DEFAULT_LOCATION: Option<Url> = None;
 const DEFAULT_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(5);
@@ -59,11 +60,18 @@ const DEFAULT_PROTOCOLS: &str = "udp-mcast,udp,tcp";
 // Equal to MTU + 8 by default to avoid incorrectly detecting an MTU sized buffer as having
 // possibly overflown our receive buffer, and triggering a doubling of the buffer sizes.
 const DEFAULT_RECEIVE_MTU: u32 = 1500 + 8;
+const DEFAULT_SEEK_FORMAT: SeekFormat = SeekFormat::Npt;
+// 0 means automatic, based on the bitrate of the stream
+const DEFAULT_UDP_BUFFER_SIZE: u32 = 0;
 
 const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
 const MAX_BIND_PORT_RETRY: u16 = 100;
 const UDP_PACKET_MAX_SIZE: u32 = 65535 - 8;
 const RTCP_ADDR_CACHE_SIZE: usize = 100;
+// Automatic UDP receive buffers hold this many seconds of data at the `b=AS` bitrate of the
+// stream, and are never smaller than the default of rtspsrc
+const UDP_BUFFER_SECONDS: u64 = 2;
+const MIN_UDP_BUFFER_SIZE: u64 = 512 * 1024;
 
 static RTCP_CAPS: LazyLock<gst::Caps> =
     LazyLock::new(|| gst::Caps::from(gst::Structure::new_empty("application/x-rtcp")));
@@ -100,6 +108,8 @@ struct Settings {
     protocols: Vec<RtspProtocol>,
     timeout: gst::ClockTime,
     receive_mtu: u32,
+    udp_buffer_size: u32,
+    seek_format: SeekFormat,
 }
 
 impl Default for Settings {
@@ -110,6 +120,8 @@ impl Default for Settings {
             timeout: DEFAULT_TIMEOUT,
             protocols: parse_protocols_str(DEFAULT_PROTOCOLS).unwrap(),
             receive_mtu: DEFAULT_RECEIVE_MTU,
+            udp_buffer_size: DEFAULT_UDP_BUFFER_SIZE,
+            seek_format: DEFAULT_SEEK_FORMAT,
         }
     }
 }
@@ -117,16 +129,162 @@ impl Default for Settings {
 #[derive(Debug)]
 enum Commands {
     Play,
-    //Pause,
+    Pause,
+    Seek {
+        start: gst::ClockTime,
+        stop: Option<gst::ClockTime>,
+        format: SeekFormat,
+        flush: bool,
+        seqnum: gst::Seqnum,
+    },
     Teardown(Option<oneshot::Sender<()>>),
     Data(rtsp_types::Data<Body>),
 }
 
+// Information about the media that is needed outside of the RTSP task, to answer queries and
+// handle seek events
+#[derive(Debug, Default)]
+struct MediaInfo {
+    start: Option<gst::ClockTime>,
+    stop: Option<gst::ClockTime>,
+    seekable: bool,
+    // All our source pads receive the same seek event from downstream, only handle it once
+    last_seek_seqnum: Option<gst::Seqnum>,
+}
+
+// RTP socket of a stream received over UDP, for the statistics
+#[derive(Debug)]
+struct UdpStream {
+    session: usize,
+    socket: Arc<UdpSocket>,
+}
+
 #[derive(Debug, Default)]
 pub struct RtspSrc {
     settings: Mutex<Settings>,
+    media_info: Mutex<MediaInfo>,
+    udp_streams: Mutex<Vec<UdpStream>>,
     task_handle: Mutex<Option<JoinHandle<()>>>,
-    command_queue: Mutex<Option<mpsc::Sender<Commands>>>,
+    command_queue: Mutex<Option<mpsc::UnboundedSender<Commands>>>,
+}
+
+// Positions are in stream time, which is 0 at the start of the media range, while the RTSP
+// requests use NPT
+impl MediaInfo {
+    fn duration(&self) -> Option<gst::ClockTime> {
+        self.stop?
+            .checked_sub(self.start.unwrap_or(gst::ClockTime::ZERO))
+    }
+
+    // Converts seek positions to NPT. The server fails PLAY requests with a range outside of the
+    // media range, which would stop the RTSP task, so seeking past the end seeks to the end
+    // instead.
+    fn seek_to_npt(
+        &self,
+        start: gst::ClockTime,
+        stop: Option<gst::ClockTime>,
+    ) -> Option<(gst::ClockTime, Option<gst::ClockTime>)> {
+        let (media_start, media_stop) = (self.start?, self.stop?);
+        let start = start.saturating_add(media_start).min(media_stop);
+        let stop = stop.map(|stop| stop.saturating_add(media_start).clamp(start, media_stop));
+        Some((start, stop))
+    }
+}
+
+// Range of the media or of the current PLAY request, in Normal Play Time. `None` means the
+// bound is open, or `now` in the case of the start.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct PlayRange {
+    start: Option<gst::ClockTime>,
+    stop: Option<gst::ClockTime>,
+}
+
+// Seek that caused a PLAY request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SeekRequest {
+    seqnum: gst::Seqnum,
+    flush: bool,
+}
+
+// PLAY and PAUSE requests state of the RTSP task
+#[derive(Debug, Default)]
+struct PlaybackState {
+    // Responses arrive in the order the requests were sent, and a seek sends both a PAUSE and
+    // a PLAY request at once
+    expected_responses: VecDeque<(Method, u32, Option<SeekRequest>)>,
+    // Whether we've sent a PLAY or a PAUSE request, to know how to resume playback
+    playing: bool,
+    paused: bool,
+    // Range and seek to PLAY when seeking while not playing
+    pending_range: Option<(Range, SeekRequest)>,
+}
+
+impl PlaybackState {
+    // Range and seek of the next PLAY request
+    fn play(&mut self) -> (Option<Range>, Option<SeekRequest>) {
+        let was_paused = self.paused;
+        self.playing = true;
+        self.paused = false;
+
+        match self.pending_range.take() {
+            Some((range, seek)) => (Some(range), Some(seek)),
+            // A PLAY without a Range resumes from the point where the media was paused
+            None if was_paused => (None, None),
+            None => (Some(Range::Npt(NptRange::From(NptTime::Now))), None),
+        }
+    }
+
+    // Whether a PAUSE request has to be sent. Live media can't be paused on the server side, we
+    // keep receiving data as before.
+    fn pause(&mut self, seekable: bool) -> bool {
+        if !self.playing || !seekable {
+            return false;
+        }
+        self.playing = false;
+        self.paused = true;
+        true
+    }
+
+    // Range of the PAUSE and PLAY requests to send for a seek while playing. Otherwise the seek
+    // is done by the next PLAY request, and flushing continues till then.
+    fn seek(&mut self, range: Range, seek: SeekRequest) -> Option<Range> {
+        if self.playing {
+            return Some(range);
+        }
+        self.pending_range = Some((range, seek));
+        None
+    }
+
+    fn request_sent(&mut self, method: Method, cseq: u32, seek: Option<SeekRequest>) {
+        self.expected_responses.push_back((method, cseq, seek));
+    }
+
+    // Method, CSeq and seek of the request a response is for. The PLAY response of a flushing
+    // seek stops flushing, unless another flushing seek is already in progress.
+    fn response_received(&mut self) -> Option<(Method, u32, Option<SeekRequest>)> {
+        let (method, cseq, seek) = self.expected_responses.pop_front()?;
+        let flushing = self
+            .expected_responses
+            .iter()
+            .any(|(m, _, seek)| *m == Method::Play && seek.is_some_and(|s| s.flush));
+        let seek = seek.map(|seek| SeekRequest {
+            flush: seek.flush && !flushing,
+            ..seek
+        });
+        Some((method, cseq, seek))
+    }
+}
+
+// Range of the media as announced in the SDP, along with what is needed to convert ranges in
+// other formats from and to Normal Play Time
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MediaRange {
+    range: PlayRange,
+    // Absolute time of the start of the media in nanoseconds since the Unix epoch, for media
+    // described with a clock range. It corresponds to NPT 0.
+    clock_base: Option<gst::ClockTime>,
+    // Timecode type for media described with a SMPTE range
+    smpte_type: Option<SmpteType>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -309,6 +467,23 @@ impl ObjectImpl for RtspSrc {
                     .default_value(DEFAULT_TIMEOUT.into())
                     .mutable_ready()
                     .build(),
+                glib::ParamSpecUInt::builder("udp-buffer-size")
+                    .nick("UDP buffer size")
+                    .blurb("Size of the kernel UDP receive buffer of each RTP stream in bytes (0 = automatic, based on the bitrate of the stream)")
+                    .maximum(i32::MAX as u32)
+                    .default_value(DEFAULT_UDP_BUFFER_SIZE)
+                    .mutable_ready()
+                    .build(),
+                glib::ParamSpecEnum::builder_with_default("seek-format", DEFAULT_SEEK_FORMAT)
+                    .nick("Seek Format")
+                    .blurb("Range format to use in PLAY requests when seeking in VOD media")
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecBoxed::builder::<gst::Structure>("stats")
+                    .nick("Statistics")
+                    .blurb("Statistics of the RTP streams received over UDP, including the number of packets dropped by the kernel because the receive buffer was full (Linux only)")
+                    .read_only()
+                    .build(),
             ]
         });
 
@@ -349,6 +524,16 @@ impl ObjectImpl for RtspSrc {
                 settings.timeout = timeout;
                 Ok(())
             }
+            "udp-buffer-size" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.udp_buffer_size = value.get().expect("type checked upstream");
+                Ok(())
+            }
+            "seek-format" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.seek_format = value.get().expect("type checked upstream");
+                Ok(())
+            }
             name => unimplemented!("Property '{name}'"),
         };
 
@@ -393,6 +578,15 @@ impl ObjectImpl for RtspSrc {
                 let settings = self.settings.lock().unwrap();
                 settings.timeout.to_value()
             }
+            "udp-buffer-size" => {
+                let settings = self.settings.lock().unwrap();
+                settings.udp_buffer_size.to_value()
+            }
+            "seek-format" => {
+                let settings = self.settings.lock().unwrap();
+                settings.seek_format.to_value()
+            }
+            "stats" => self.create_stats().to_value(),
             name => unimplemented!("Property '{name}'"),
         }
     }
@@ -450,9 +644,11 @@ impl ElementImpl for RtspSrc {
                 })?;
             }
             gst::StateChange::PausedToPlaying => {
-                let cmd_queue = self.cmd_queue();
                 //self.async_start().map_err(|_| gst::StateChangeError)?;
-                RUNTIME.spawn(async move { cmd_queue.send(Commands::Play).await });
+                let _ = self.cmd_queue().send(Commands::Play);
+            }
+            gst::StateChange::PlayingToPaused => {
+                let _ = self.cmd_queue().send(Commands::Pause);
             }
             _ => {}
         }
@@ -498,6 +694,13 @@ impl ElementImpl for RtspSrc {
 
         Ok(ret)
     }
+
+    fn send_event(&self, event: gst::Event) -> bool {
+        match event.view() {
+            gst::EventView::Seek(seek) => self.handle_seek(seek),
+            _ => self.parent_send_event(event),
+        }
+    }
 }
 
 impl BinImpl for RtspSrc {}
@@ -526,7 +729,7 @@ type RtspSink = Pin<Box<dyn Sink<Message<Body>, Error = std::io::Error> + Send>>
 
 impl RtspSrc {
     #[track_caller]
-    fn cmd_queue(&self) -> mpsc::Sender<Commands> {
+    fn cmd_queue(&self) -> mpsc::UnboundedSender<Commands> {
         self.command_queue.lock().unwrap().as_ref().unwrap().clone()
     }
 
@@ -546,7 +749,9 @@ impl RtspSrc {
 
         let mut task_handle = self.task_handle.lock().unwrap();
 
-        let (tx, rx) = mpsc::channel(1);
+        // Unbounded so that commands can be sent synchronously from any thread, and are received
+        // in the order in which they were sent
+        let (tx, rx) = mpsc::unbounded_channel();
         {
             let mut cmd_queue_opt = self.command_queue.lock().unwrap();
             debug_assert!(cmd_queue_opt.is_none());
@@ -591,6 +796,7 @@ impl RtspSrc {
             for h in state.handles {
                 let _ = h.await;
             }
+            task_src.udp_streams.lock().unwrap().clear();
             let obj = task_src.obj();
             for e in obj.iterate_sorted() {
                 let Ok(e) = e else {
@@ -640,7 +846,7 @@ impl RtspSrc {
 
         RUNTIME.block_on(async {
             let (tx, rx) = oneshot::channel();
-            if let Ok(()) = cmd_queue.send(Commands::Teardown(Some(tx))).await {
+            if let Ok(()) = cmd_queue.send(Commands::Teardown(Some(tx))) {
                 if let Err(_elapsed) = time::timeout(Duration::from_millis(500), rx).await {
                     gst::warning!(
                         CAT,
@@ -655,6 +861,7 @@ impl RtspSrc {
             let _ = RUNTIME.block_on(join_handle);
         }
 
+        *self.media_info.lock().unwrap() = MediaInfo::default();
         self.command_queue.lock().unwrap().take();
 
         gst::info!(CAT, imp = self, "Stopped");
@@ -662,6 +869,166 @@ impl RtspSrc {
         Ok(())
     }
 
+    fn create_stats(&self) -> gst::Structure {
+        let udp_streams = self.udp_streams.lock().unwrap();
+        let streams = udp_streams.iter().map(|stream| {
+            let sock = socket2::SockRef::from(&*stream.socket);
+            let udp_buffer_size = sock.recv_buffer_size().map(actual_recv_buffer_size);
+            let drops = udp_drops(&stream.socket);
+            if let Err(err) = &drops {
+                gst::trace!(CAT, imp = self, "Failed to get UDP drops: {err:?}");
+            }
+
+            gst::Structure::builder("application/x-rtspsrc2-stream-stats")
+                .field("session", stream.session as u32)
+                .field_if_some(
+                    "udp-buffer-size",
+                    udp_buffer_size.ok().map(|size| size as u32),
+                )
+                .field_if_some("drops", drops.ok())
+                .build()
+                .to_send_value()
+        });
+
+        gst::Structure::builder("application/x-rtspsrc2-stats")
+            .field("streams", gst::Array::new(streams))
+            .build()
+    }
+
+    fn update_media_info(&self, range: PlayRange, seekable: bool) {
+        let duration = {
+            let mut media_info = self.media_info.lock().unwrap();
+            media_info.start = range.start;
+            media_info.stop = range.stop;
+            media_info.seekable = seekable;
+            media_info.duration()
+        };
+
+        gst::info!(
+            CAT,
+            imp = self,
+            "Media duration: {}, seekable: {seekable}",
+            duration.display()
+        );
+
+        if duration.is_some() {
+            let obj = self.obj();
+            let _ = obj.post_message(gst::message::DurationChanged::builder().src(&*obj).build());
+        }
+    }
+
+    fn handle_seek(&self, seek: &gst::event::Seek) -> bool {
+        let (rate, flags, start_type, start, stop_type, stop) = seek.get();
+        let seqnum = seek.seqnum();
+
+        {
+            let media_info = self.media_info.lock().unwrap();
+            if !media_info.seekable {
+                gst::debug!(CAT, imp = self, "Media is not seekable");
+                return false;
+            }
+            if media_info.last_seek_seqnum == Some(seqnum) {
+                gst::debug!(CAT, imp = self, "Already handled seek {seqnum:?}");
+                return true;
+            }
+        }
+
+        // TODO: use the Scale / Speed headers for trick modes
+        if rate != 1.0 {
+            gst::warning!(CAT, imp = self, "Unsupported seek rate {rate}");
+            return false;
+        }
+
+        let (gst::GenericFormattedValue::Time(start), gst::GenericFormattedValue::Time(stop)) =
+            (start, stop)
+        else {
+            gst::warning!(
+                CAT,
+                imp = self,
+                "Unsupported seek format {:?}",
+                start.format()
+            );
+            return false;
+        };
+
+        let start = match (start_type, start) {
+            (gst::SeekType::Set, Some(start)) => start,
+            _ => {
+                gst::warning!(CAT, imp = self, "Unsupported seek start {start_type:?}");
+                return false;
+            }
+        };
+        let stop = match stop_type {
+            gst::SeekType::Set => stop,
+            _ => None,
+        };
+
+        let (start, stop) = {
+            let mut media_info = self.media_info.lock().unwrap();
+            let Some((start, stop)) = media_info.seek_to_npt(start, stop) else {
+                gst::warning!(CAT, imp = self, "Media has no range, can't seek");
+                return false;
+            };
+            media_info.last_seek_seqnum = Some(seqnum);
+            (start, stop)
+        };
+        gst::debug!(
+            CAT,
+            imp = self,
+            "Seeking to NPT {start} - {}",
+            stop.display()
+        );
+
+        let format = self.settings.lock().unwrap().seek_format;
+        let flush = flags.contains(gst::SeekFlags::FLUSH);
+        let _ = self.cmd_queue().send(Commands::Seek {
+            start,
+            stop,
+            format,
+            flush,
+            seqnum,
+        });
+
+        true
+    }
+
+    fn src_event(&self, pad: &gst::GhostPad, event: gst::Event) -> bool {
+        gst::log!(CAT, obj = pad, "Handling event {event:?}");
+
+        match event.view() {
+            gst::EventView::Seek(seek) => self.handle_seek(seek),
+            _ => gst::Pad::event_default(pad, Some(&*self.obj()), event),
+        }
+    }
+
+    fn src_query(&self, pad: &gst::GhostPad, query: &mut gst::QueryRef) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj = pad, "Handling query {query:?}");
+
+        match query.view_mut() {
+            QueryViewMut::Duration(q) if q.format() == gst::Format::Time => {
+                let duration = self.media_info.lock().unwrap().duration();
+                if duration.is_some() {
+                    q.set(duration);
+                    true
+                } else {
+                    gst::Pad::query_default(pad, Some(&*self.obj()), query)
+                }
+            }
+            QueryViewMut::Seeking(q) if q.format() == gst::Format::Time => {
+                let media_info = self.media_info.lock().unwrap();
+                q.set(
+                    media_info.seekable,
+                    gst::ClockTime::ZERO,
+                    media_info.duration(),
+                );
+                true
+            }
+            _ => gst::Pad::query_default(pad, Some(&*self.obj()), query),
+        }
+    }
+
     fn make_rtp_appsrc(
         &self,
         rtpsession_n: usize,
@@ -695,6 +1062,12 @@ impl RtspSrc {
         let templ = obj.pad_template("stream_%u").unwrap();
         let ghostpad = gst::GhostPad::builder_from_template(&templ)
             .name(format!("stream_{rtpsession_n}"))
+            .event_function(|pad, parent, event| {
+                RtspSrc::catch_panic_pad_function(parent, || false, |imp| imp.src_event(pad, event))
+            })
+            .query_function(|pad, parent, query| {
+                RtspSrc::catch_panic_pad_function(parent, || false, |imp| imp.src_query(pad, query))
+            })
             .build();
         gst::info!(CAT, "Adding ghost srcpad {}", ghostpad.name());
         obj.add_pad(&ghostpad)
@@ -736,10 +1109,7 @@ impl RtspSrc {
         let cmd_tx_eos = self.cmd_queue();
         let cbs = gst_app::app_sink::AppSinkCallbacks::builder()
             .eos(move |_appsink| {
-                let cmd_tx = cmd_tx_eos.clone();
-                RUNTIME.spawn(async move {
-                    let _ = cmd_tx.send(Commands::Teardown(None)).await;
-                });
+                let _ = cmd_tx_eos.send(Commands::Teardown(None));
             })
             .new_sample(on_rtcp)
             .build();
@@ -785,7 +1155,7 @@ impl RtspSrc {
     async fn rtsp_task(
         &self,
         state: &mut RtspTaskState,
-        mut cmd_rx: mpsc::Receiver<Commands>,
+        mut cmd_rx: mpsc::UnboundedReceiver<Commands>,
     ) -> Result<()> {
         let cmd_tx = self.cmd_queue();
 
@@ -796,6 +1166,14 @@ impl RtspSrc {
 
         // DESCRIBE
         state.describe().await?;
+        self.update_media_info(
+            state
+                .media_range
+                .as_ref()
+                .map(|r| r.range)
+                .unwrap_or_default(),
+            state.is_seekable(),
+        );
 
         let mut session: Option<Session> = None;
         // SETUP streams (TCP interleaved)
@@ -816,6 +1194,10 @@ impl RtspSrc {
             .add_to(obj.upcast_ref::<gst::Bin>())
             .expect("Adding the manager cannot fail");
 
+        // The server doesn't send any data before the first PLAY request and while the media is
+        // paused, so the UDP receive tasks must not time out during that time
+        let (paused_tx, paused_rx) = watch::channel(true);
+
         let mut tcp_interleave_appsrcs = HashMap::new();
         for (rtpsession_n, p) in state.setup_params.iter_mut().enumerate() {
             let (tx, rx) = mpsc::channel(1);
@@ -875,9 +1257,20 @@ impl RtspSrc {
                         }
                     };
 
+                    set_udp_buffer_size(
+                        &rtp_socket,
+                        udp_buffer_size(settings.udp_buffer_size, p.bandwidth),
+                    );
+                    let rtp_socket = Arc::new(rtp_socket);
+                    self.udp_streams.lock().unwrap().push(UdpStream {
+                        session: rtpsession_n,
+                        socket: rtp_socket.clone(),
+                    });
+
                     let rtp_appsrc = self.make_rtp_appsrc(rtpsession_n, &p.caps, &manager)?;
                     p.rtp_appsrc = Some(rtp_appsrc.clone());
                     // Spawn RTP udp receive task
+                    let paused = paused_rx.clone();
                     state.handles.push(RUNTIME.spawn(async move {
                         udp_rtp_task(
                             &rtp_socket,
@@ -885,6 +1278,7 @@ impl RtspSrc {
                             settings.timeout,
                             settings.receive_mtu,
                             None,
+                            paused,
                         )
                         .await
                     }));
@@ -927,9 +1321,20 @@ impl RtspSrc {
                         _ => (None, None),
                     };
 
+                    set_udp_buffer_size(
+                        &rtp_socket,
+                        udp_buffer_size(settings.udp_buffer_size, p.bandwidth),
+                    );
+                    let rtp_socket = Arc::new(rtp_socket);
+                    self.udp_streams.lock().unwrap().push(UdpStream {
+                        session: rtpsession_n,
+                        socket: rtp_socket.clone(),
+                    });
+
                     // Spawn RTP udp receive task
                     let rtp_appsrc = self.make_rtp_appsrc(rtpsession_n, &p.caps, &manager)?;
                     p.rtp_appsrc = Some(rtp_appsrc.clone());
+                    let paused = paused_rx.clone();
                     state.handles.push(RUNTIME.spawn(async move {
                         udp_rtp_task(
                             &rtp_socket,
@@ -937,6 +1342,7 @@ impl RtspSrc {
                             settings.timeout,
                             settings.receive_mtu,
                             rtp_sender_addr,
+                            paused,
                         )
                         .await
                     }));
@@ -1021,7 +1427,7 @@ impl RtspSrc {
             }
         });
 
-        let mut expected_response: Option<(Method, u32)> = None;
+        let mut playback = PlaybackState::default();
         loop {
             tokio::select! {
                 msg = state.stream.next() => match msg {
@@ -1043,9 +1449,13 @@ impl RtspSrc {
                         let bufref = buffer.make_mut();
                         bufref.set_dts(t);
                         // TODO: Allow unlinked source pads
-                        if let Err(err) = appsrc.push_buffer(buffer) {
-                            gst::error!(CAT, "Failed to push buffer on pad {} for channel {}", appsrc.name(), channel_id);
-                            return Err(err.into());
+                        match appsrc.push_buffer(buffer) {
+                            // Data received while flushing for a seek is from before the seek
+                            Ok(_) | Err(gst::FlowError::Flushing) => (),
+                            Err(err) => {
+                                gst::error!(CAT, "Failed to push buffer on pad {} for channel {}", appsrc.name(), channel_id);
+                                return Err(err.into());
+                            }
                         }
                     }
                     Some(Ok(rtsp_types::Message::Request(req))) => {
@@ -1054,7 +1464,7 @@ impl RtspSrc {
                     }
                     Some(Ok(rtsp_types::Message::Response(rsp))) => {
                         gst::debug!(CAT, "<-- {rsp:#?}");
-                        let Some((expected, cseq)) = &expected_response else {
+                        let Some((expected, cseq, seek)) = playback.response_received() else {
                             continue;
                         };
                         let Some(s) = &session else {
@@ -1062,10 +1472,11 @@ impl RtspSrc {
                         };
                         match expected {
                             Method::Play => {
-                                state.play_response(&rsp, *cseq, s).await?;
+                                state.play_response(&rsp, cseq, s, seek).await?;
                                 self.post_complete("request", "PLAY response received");
                             }
-                            Method::Teardown => state.teardown_response(&rsp, *cseq, s).await?,
+                            Method::Pause => state.pause_response(&rsp, cseq, s).await?,
+                            Method::Teardown => state.teardown_response(&rsp, cseq, s).await?,
                             m => unreachable!("BUG: unexpected response method: {m:?}"),
                         };
                     }
@@ -1085,11 +1496,45 @@ impl RtspSrc {
                         let Some(s) = &session else {
                             return Err(RtspError::InvalidMessage("Can't PLAY, no SETUP").into());
                         };
+                        let (range, seek) = playback.play();
                         self.post_start("request", "PLAY request sent");
-                        let cseq = state.play(s).await.inspect_err(|_err| {
+                        let cseq = state.play(s, range).await.inspect_err(|_err| {
                             self.post_cancelled("request", "PLAY request cancelled");
                         })?;
-                        expected_response = Some((Method::Play, cseq));
+                        playback.request_sent(Method::Play, cseq, seek);
+                        paused_tx.send_replace(false);
+                    },
+                    Commands::Pause => {
+                        if !playback.pause(state.is_seekable()) {
+                            continue;
+                        }
+                        let Some(s) = &session else {
+                            return Err(RtspError::InvalidMessage("Can't PAUSE, no SETUP").into());
+                        };
+                        let cseq = state.pause(s).await?;
+                        playback.request_sent(Method::Pause, cseq, None);
+                        paused_tx.send_replace(true);
+                    },
+                    Commands::Seek { start, stop, format, flush, seqnum } => {
+                        let Some(s) = &session else {
+                            return Err(RtspError::InvalidMessage("Can't seek, no SETUP").into());
+                        };
+                        let range = state.seek_range(format, start, stop);
+                        let seek = SeekRequest { seqnum, flush };
+                        if flush {
+                            state.flush_start(seqnum);
+                        }
+                        if let Some(range) = playback.seek(range, seek) {
+                            // Pause first, otherwise the server will queue the new PLAY
+                            // request till the end of the current range
+                            let cseq = state.pause(s).await?;
+                            playback.request_sent(Method::Pause, cseq, None);
+                            self.post_start("request", "PLAY request sent");
+                            let cseq = state.play(s, Some(range)).await.inspect_err(|_err| {
+                                self.post_cancelled("request", "PLAY request cancelled");
+                            })?;
+                            playback.request_sent(Method::Play, cseq, Some(seek));
+                        }
                     },
                     Commands::Teardown(tx) => {
                         gst::info!(CAT, "Received Teardown command");
@@ -1214,6 +1659,8 @@ struct RtspTaskState {
     content_base_or_location: Option<String>,
     aggregate_control: Option<Url>,
     sdp: Option<sdp_types::Session>,
+    pause_supported: bool,
+    media_range: Option<MediaRange>,
 
     stream:
         Pin<Box<dyn Stream<Item = Result<Message<Body>, super::tcp_message::ReadError>> + Send>>,
@@ -1228,6 +1675,8 @@ struct RtspSetupParams {
     transport: RtspTransportInfo,
     rtp_appsrc: Option<gst_app::AppSrc>,
     caps: gst::Caps,
+    // Bitrate of the stream in kbit/s, from the SDP `b=AS` line
+    bandwidth: Option<u64>,
 }
 
 impl RtspTaskState {
@@ -1239,6 +1688,8 @@ impl RtspTaskState {
             content_base_or_location: None,
             aggregate_control: None,
             sdp: None,
+            pause_supported: false,
+            media_range: None,
             stream,
             sink,
             setup_params: Vec::new(),
@@ -1246,6 +1697,60 @@ impl RtspTaskState {
         }
     }
 
+    fn is_seekable(&self) -> bool {
+        self.pause_supported
+            && matches!(
+                self.media_range,
+                Some(MediaRange {
+                    range: PlayRange {
+                        start: Some(_),
+                        stop: Some(_)
+                    },
+                    ..
+                })
+            )
+    }
+
+    // Range to request in the PLAY request for a seek, in the requested format if it can be
+    // expressed in it and in NPT otherwise
+    fn seek_range(
+        &self,
+        format: SeekFormat,
+        start: gst::ClockTime,
+        stop: Option<gst::ClockTime>,
+    ) -> Range {
+        self.media_range
+            .as_ref()
+            .and_then(|r| r.seek_range(format, start, stop))
+            .unwrap_or_else(|| {
+                gst::warning!(
+                    CAT,
+                    "Can't express seek to {start} as {format:?} range, using NPT"
+                );
+                npt_range(start, stop)
+            })
+    }
+
+    fn rtp_appsrcs(&self) -> impl Iterator<Item = &gst_app::AppSrc> {
+        self.setup_params
+            .iter()
+            .filter_map(|p| p.rtp_appsrc.as_ref())
+    }
+
+    fn flush_start(&self, seqnum: gst::Seqnum) {
+        gst::debug!(CAT, "Flush start {seqnum:?}");
+        for appsrc in self.rtp_appsrcs() {
+            appsrc.send_event(gst::event::FlushStart::builder().seqnum(seqnum).build());
+        }
+    }
+
+    fn flush_stop(&self, seqnum: gst::Seqnum) {
+        gst::debug!(CAT, "Flush stop {seqnum:?}");
+        for appsrc in self.rtp_appsrcs() {
+            appsrc.send_event(gst::event::FlushStop::builder(true).seqnum(seqnum).build());
+        }
+    }
+
     #[allow(clippy::result_large_err)]
     fn check_response(
         rsp: &Response<Body>,
@@ -1333,6 +1838,8 @@ impl RtspTaskState {
             Method::Play,
             Method::Teardown,
         ];
+        self.pause_supported = methods.contains(&Method::Pause);
+
         let mut unsupported = Vec::new();
         for method in &needed {
             if !methods.contains(method) {
@@ -1385,10 +1892,25 @@ impl RtspTaskState {
             .map(|v| v.to_string());
 
         gst::info!(CAT, "{}", std::str::from_utf8(rsp.body()).unwrap());
-        // TODO: read range attribute from SDP for VOD use-cases
         let sdp = sdp_types::Session::parse(rsp.body())?;
         gst::debug!(CAT, "{sdp:#?}");
 
+        // Only VOD media has a range with a start and a stop time. Use the range of the first
+        // media that has one if there's no session-level range.
+        self.media_range = sdp
+            .get_first_attribute_value("range")
+            .ok()
+            .flatten()
+            .or_else(|| {
+                sdp.medias
+                    .iter()
+                    .find_map(|m| m.get_first_attribute_value("range").ok().flatten())
+            })
+            .and_then(parse_range)
+            .as_ref()
+            .and_then(MediaRange::from_range);
+        gst::debug!(CAT, "Media range: {:?}", self.media_range);
+
         self.sdp.replace(sdp);
         Ok(())
     }
@@ -1462,7 +1984,7 @@ impl RtspTaskState {
             .and_then(|v| sdp::parse_control_path(v, &base));
         let mut b = gst::Structure::builder("application/x-rtp");
 
-        // TODO: parse range for VOD
+        // range is parsed in describe()
         let skip_attrs = ["control", "range"];
         for sdp_types::Attribute { attribute, value } in &sdp.attributes {
             if skip_attrs.contains(&attribute.as_str()) {
@@ -1526,6 +2048,14 @@ impl RtspTaskState {
                 continue;
             }
 
+            // Used to size the UDP receive buffers
+            let bandwidth = m
+                .bandwidths
+                .iter()
+                .chain(&sdp.bandwidths)
+                .find(|b| b.bwtype == "AS")
+                .map(|b| b.bandwidth);
+
             // SETUP
             let mut rtp_socket: Option<UdpSocket> = None;
             let mut rtcp_socket: Option<UdpSocket> = None;
@@ -1697,20 +2227,25 @@ impl RtspTaskState {
                 transport: parsed_transport,
                 rtp_appsrc: None,
                 caps,
+                bandwidth,
             });
         }
         Ok(setup_params)
     }
 
-    async fn play(&mut self, session: &Session) -> Result<u32, RtspError> {
+    async fn play(&mut self, session: &Session, range: Option<Range>) -> Result<u32, RtspError> {
         self.cseq += 1;
         let request_uri = self.aggregate_control.as_ref().unwrap_or(&self.url).clone();
         let req = Request::builder(Method::Play, self.version)
             .typed_header::<CSeq>(&self.cseq.into())
-            .typed_header::<Range>(&Range::Npt(NptRange::From(NptTime::Now)))
             .header(USER_AGENT, DEFAULT_USER_AGENT)
             .request_uri(request_uri)
             .typed_header::<Session>(session);
+        let req = if let Some(range) = range {
+            req.typed_header::<Range>(&range)
+        } else {
+            req
+        };
 
         let req = req.build(Body::default());
         gst::debug!(CAT, "-->> {req:#?}");
@@ -1723,6 +2258,7 @@ impl RtspTaskState {
         rsp: &Response<Body>,
         cseq: u32,
         session: &Session,
+        seek: Option<SeekRequest>,
     ) -> Result<(), RtspError> {
         Self::check_response(rsp, cseq, Method::Play, Some(session))?;
         if let Some(RtpInfos::V1(rtpinfos)) = rsp.typed_header::<RtpInfos>()? {
@@ -1749,6 +2285,87 @@ impl RtspTaskState {
         } else {
             gst::warning!(CAT, "No RTPInfos V1 header in PLAY response");
         };
+
+        // Data received till now is from before the seek, stop flushing right before the segment
+        // for the new range
+        if let Some(SeekRequest {
+            seqnum,
+            flush: true,
+        }) = seek
+        {
+            self.flush_stop(seqnum);
+        }
+
+        if !self.is_seekable() {
+            return Ok(());
+        }
+
+        let range = rsp
+            .header(&RANGE)
+            .and_then(|v| parse_range(v.as_str()))
+            .and_then(|r| self.media_range.as_ref()?.to_npt(&r));
+        let Some(PlayRange {
+            start: Some(start),
+            stop,
+        }) = range
+        else {
+            gst::warning!(
+                CAT,
+                "No usable Range header in PLAY response, can't update segment"
+            );
+            return Ok(());
+        };
+        gst::debug!(CAT, "Playing range {start} - {}", stop.display());
+        let media_start = self
+            .media_range
+            .as_ref()
+            .and_then(|r| r.range.start)
+            .unwrap_or(gst::ClockTime::ZERO);
+
+        for appsrc in self.rtp_appsrcs() {
+            // Used by the jitterbuffer to detect the end of the range
+            let mut caps = appsrc.caps().unwrap();
+            let capsref = caps.make_mut();
+            capsref.set("npt-start", start.nseconds());
+            if let Some(stop) = stop {
+                capsref.set("npt-stop", stop.nseconds());
+            }
+            appsrc.set_caps(Some(&caps));
+
+            let segment = play_segment(appsrc.current_running_time(), start, media_start);
+            // Segments following a seek must have the seek's seqnum
+            appsrc.send_event(
+                gst::event::Segment::builder(&segment)
+                    .seqnum_if_some(seek.map(|s| s.seqnum))
+                    .build(),
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn pause(&mut self, session: &Session) -> Result<u32, RtspError> {
+        self.cseq += 1;
+        let request_uri = self.aggregate_control.as_ref().unwrap_or(&self.url).clone();
+        let req = Request::builder(Method::Pause, self.version)
+            .typed_header::<CSeq>(&self.cseq.into())
+            .header(USER_AGENT, DEFAULT_USER_AGENT)
+            .request_uri(request_uri)
+            .typed_header::<Session>(session);
+
+        let req = req.build(Body::default());
+        gst::debug!(CAT, "-->> {req:#?}");
+        self.sink.send(req.into()).await?;
+        Ok(self.cseq)
+    }
+
+    async fn pause_response(
+        &mut self,
+        rsp: &Response<Body>,
+        cseq: u32,
+        session: &Session,
+    ) -> Result<(), RtspError> {
+        Self::check_response(rsp, cseq, Method::Pause, Some(session))?;
         Ok(())
     }
 
@@ -1778,6 +2395,24 @@ impl RtspTaskState {
     }
 }
 
+// Segment for data received from `running_time` on, for a PLAY range starting at `npt_start`.
+// Buffers are timestamped with the running time at which they were received, so the running
+// time at the start of the range is mapped to its stream time, which is 0 at `media_start`.
+fn play_segment(
+    running_time: Option<gst::ClockTime>,
+    npt_start: gst::ClockTime,
+    media_start: gst::ClockTime,
+) -> gst::FormattedSegment<gst::ClockTime> {
+    let mut segment = gst::FormattedSegment::<gst::ClockTime>::new();
+    if let Some(running_time) = running_time {
+        segment.set_start(running_time);
+        segment.set_base(running_time);
+        segment.set_position(running_time);
+    }
+    segment.set_time(npt_start.saturating_sub(media_start));
+    segment
+}
+
 fn bind_port(port: u16, is_ipv4: bool) -> Result<UdpSocket, std::io::Error> {
     let domain = if is_ipv4 {
         socket2::Domain::IPV4
@@ -1805,6 +2440,80 @@ fn bind_port(port: u16, is_ipv4: bool) -> Result<UdpSocket, std::io::Error> {
     UdpSocket::from_std(sock.into())
 }
 
+// Size of the kernel receive buffer of an RTP stream with a `b=AS` bitrate of `bandwidth` kbit/s,
+// if `size` is 0 (automatic)
+fn udp_buffer_size(size: u32, bandwidth: Option<u64>) -> usize {
+    if size != 0 {
+        return size as usize;
+    }
+    let size = bandwidth.map_or(0, |kbps| kbps.saturating_mul(1000 / 8 * UDP_BUFFER_SECONDS));
+    // The kernel stores the size in an int
+    size.clamp(MIN_UDP_BUFFER_SIZE, i32::MAX as u64) as usize
+}
+
+// Receive buffer size that was set, from the size reported by the OS. Linux reports twice the size
+// that was set, to account for its bookkeeping overhead.
+fn actual_recv_buffer_size(reported: usize) -> usize {
+    if cfg!(target_os = "linux") {
+        reported / 2
+    } else {
+        reported
+    }
+}
+
+fn set_udp_buffer_size(socket: &UdpSocket, size: usize) {
+    let sock = socket2::SockRef::from(socket);
+    if let Err(err) = sock.set_recv_buffer_size(size) {
+        gst::warning!(
+            CAT,
+            "Failed to set UDP receive buffer size to {size}: {err:?}"
+        );
+        return;
+    }
+    // The OS caps the size, e.g. to net.core.rmem_max on Linux
+    match sock.recv_buffer_size().map(actual_recv_buffer_size) {
+        Ok(actual) if actual < size => gst::warning!(
+            CAT,
+            "UDP receive buffer size is {actual} instead of {size}, packets may be dropped. Raise the OS limit, e.g. net.core.rmem_max on Linux"
+        ),
+        Ok(actual) => gst::debug!(CAT, "UDP receive buffer size is {actual}"),
+        Err(err) => gst::warning!(CAT, "Failed to get UDP receive buffer size: {err:?}"),
+    }
+}
+
+// Number of packets the kernel dropped because the receive buffer of the socket was full
+#[cfg(target_os = "linux")]
+fn udp_drops(socket: &UdpSocket) -> std::io::Result<u32> {
+    use std::os::fd::AsRawFd;
+
+    let mut meminfo = [0u32; libc::SK_MEMINFO_DROPS as usize + 1];
+    let mut len = std::mem::size_of_val(&meminfo) as libc::socklen_t;
+    // SAFETY: `meminfo` is valid for writes of `len` bytes
+    let res = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_MEMINFO,
+            meminfo.as_mut_ptr().cast(),
+            &mut len,
+        )
+    };
+    if res != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // Kernels before 4.6 don't report the drops
+    if (len as usize) < std::mem::size_of_val(&meminfo) {
+        return Err(std::io::ErrorKind::Unsupported.into());
+    }
+
+    Ok(meminfo[libc::SK_MEMINFO_DROPS as usize])
+}
+
+#[cfg(not(target_os = "linux"))]
+fn udp_drops(_socket: &UdpSocket) -> std::io::Result<u32> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
 async fn bind_start_port(port: u16, is_ipv4: bool) -> (UdpSocket, u16) {
     let mut next_port = port;
     loop {
@@ -1830,6 +2539,297 @@ async fn bind_start_port(port: u16, is_ipv4: bool) -> (UdpSocket, u16) {
     }
 }
 
+// Parses a Range header or SDP `range` attribute value such as `npt=0-30.5`,
+// `smpte-25=10:07:00-10:07:33:05.01` or `clock=19961108T142300Z-`. rtsp-types doesn't handle
+// parameters such as `;time=`, so they are ignored.
+fn parse_range(s: &str) -> Option<Range> {
+    let s = s.split(';').next()?.trim();
+    // rtsp-types fails to parse SMPTE ranges that have a start or a stop time
+    if s.starts_with("smpte") {
+        return parse_smpte_range(s).map(Range::Smpte);
+    }
+    match s.parse::<Range>() {
+        Ok(Range::Other(_)) | Err(_) => None,
+        Ok(range) => Some(range),
+    }
+}
+
+fn parse_smpte_range(s: &str) -> Option<SmpteRange> {
+    let Some((ty, range)) = s.split_once('=') else {
+        return s.parse().ok().map(SmpteRange::Empty);
+    };
+    let ty = ty.parse::<SmpteType>().ok()?;
+    let (start, stop) = range.split_once('-')?;
+    let parse_time = |t: &str| -> Option<Option<SmpteTime>> {
+        if t.is_empty() {
+            Some(None)
+        } else {
+            t.parse().ok().map(Some)
+        }
+    };
+
+    match (parse_time(start)?, parse_time(stop)?) {
+        (Some(start), Some(stop)) => Some(SmpteRange::FromTo(ty, start, stop)),
+        (Some(start), None) => Some(SmpteRange::From(ty, start)),
+        (None, Some(stop)) => Some(SmpteRange::To(ty, stop)),
+        (None, None) => None,
+    }
+}
+
+impl MediaRange {
+    fn from_range(range: &Range) -> Option<Self> {
+        // Clock ranges are absolute, NPT 0 corresponds to the start of the media
+        let clock_base = match range {
+            Range::Utc(UtcRange::From(start) | UtcRange::FromTo(start, _)) => {
+                Some(utc_time_to_unix(*start)?)
+            }
+            _ => None,
+        };
+        let smpte_type = match range {
+            Range::Smpte(
+                SmpteRange::Empty(ty)
+                | SmpteRange::From(ty, _)
+                | SmpteRange::FromTo(ty, _, _)
+                | SmpteRange::To(ty, _),
+            ) => Some(ty.clone()),
+            _ => None,
+        };
+
+        Some(MediaRange {
+            range: range_to_npt(range, clock_base)?,
+            clock_base,
+            smpte_type,
+        })
+    }
+
+    // Converts a range in any format, e.g. from the Range header of a PLAY response, to NPT
+    fn to_npt(&self, range: &Range) -> Option<PlayRange> {
+        range_to_npt(range, self.clock_base)
+    }
+
+    // Range for a PLAY request from `start` to `stop`, or `None` if it can't be expressed in
+    // `format` for this media
+    fn seek_range(
+        &self,
+        format: SeekFormat,
+        start: gst::ClockTime,
+        stop: Option<gst::ClockTime>,
+    ) -> Option<Range> {
+        match format {
+            SeekFormat::Npt => Some(npt_range(start, stop)),
+            SeekFormat::Smpte => {
+                // Use the timecode type of the SDP range, if any
+                let ty = self.smpte_type.clone().unwrap_or(SmpteType::Smpte);
+                let start = smpte_time_from_clock_time(&ty, start)?;
+                let range = match stop {
+                    Some(stop) => {
+                        let stop = smpte_time_from_clock_time(&ty, stop)?;
+                        SmpteRange::FromTo(ty, start, stop)
+                    }
+                    None => SmpteRange::From(ty, start),
+                };
+                Some(Range::Smpte(range))
+            }
+            SeekFormat::Clock => {
+                // Absolute times are only known for media described with a clock range
+                let clock_base = self.clock_base?;
+                let to_utc = |t: gst::ClockTime| utc_time_from_unix(clock_base.checked_add(t)?);
+                let range = match stop {
+                    Some(stop) => UtcRange::FromTo(to_utc(start)?, to_utc(stop)?),
+                    None => UtcRange::From(to_utc(start)?),
+                };
+                Some(Range::Utc(range))
+            }
+        }
+    }
+}
+
+// Converts a range to NPT. `clock_base` is the absolute time of NPT 0, needed for clock ranges.
+fn range_to_npt(range: &Range, clock_base: Option<gst::ClockTime>) -> Option<PlayRange> {
+    // Fails if any of the bounds can't be converted
+    fn convert<T>(
+        start: Option<T>,
+        stop: Option<T>,
+        f: impl Fn(T) -> Option<gst::ClockTime>,
+    ) -> Option<PlayRange> {
+        Some(PlayRange {
+            start: match start {
+                Some(t) => Some(f(t)?),
+                None => None,
+            },
+            stop: match stop {
+                Some(t) => Some(f(t)?),
+                None => None,
+            },
+        })
+    }
+
+    match range {
+        Range::Npt(range) => {
+            let (start, stop) = match *range {
+                NptRange::Empty => (None, None),
+                NptRange::From(start) => (Some(start), None),
+                NptRange::FromTo(start, stop) => (Some(start), Some(stop)),
+                NptRange::To(stop) => (None, Some(stop)),
+            };
+            // `now` is an open bound
+            let is_time = |t: &NptTime| *t != NptTime::Now;
+            convert(
+                start.filter(is_time),
+                stop.filter(is_time),
+                npt_time_to_clock_time,
+            )
+        }
+        Range::Smpte(range) => {
+            let (ty, start, stop) = match range {
+                SmpteRange::Empty(ty) => (ty, None, None),
+                SmpteRange::From(ty, start) => (ty, Some(*start), None),
+                SmpteRange::FromTo(ty, start, stop) => (ty, Some(*start), Some(*stop)),
+                SmpteRange::To(ty, stop) => (ty, None, Some(*stop)),
+            };
+            convert(start, stop, |t| smpte_time_to_clock_time(ty, t))
+        }
+        Range::Utc(range) => {
+            let clock_base = clock_base?;
+            let (start, stop) = match *range {
+                UtcRange::Empty => (None, None),
+                UtcRange::From(start) => (Some(start), None),
+                UtcRange::FromTo(start, stop) => (Some(start), Some(stop)),
+                UtcRange::To(stop) => (None, Some(stop)),
+            };
+            convert(start, stop, |t| {
+                utc_time_to_unix(t)?.checked_sub(clock_base)
+            })
+        }
+        Range::Other(_) => None,
+    }
+}
+
+fn npt_range(start: gst::ClockTime, stop: Option<gst::ClockTime>) -> Range {
+    let start = npt_time_from_clock_time(start);
+    let range = match stop {
+        Some(stop) => NptRange::FromTo(start, npt_time_from_clock_time(stop)),
+        None => NptRange::From(start),
+    };
+    Range::Npt(range)
+}
+
+fn npt_time_to_clock_time(t: NptTime) -> Option<gst::ClockTime> {
+    let (seconds, nanoseconds) = match t {
+        NptTime::Now => return None,
+        NptTime::Seconds(seconds, nanoseconds) => (seconds, nanoseconds),
+        NptTime::Hms(hours, minutes, seconds, nanoseconds) => (
+            hours
+                .checked_mul(3600)?
+                .checked_add(minutes as u64 * 60 + seconds as u64)?,
+            nanoseconds,
+        ),
+    };
+    seconds
+        .checked_mul(gst::ClockTime::SECOND.nseconds())?
+        .checked_add(nanoseconds.unwrap_or(0) as u64)
+        .map(gst::ClockTime::from_nseconds)
+}
+
+fn npt_time_from_clock_time(t: gst::ClockTime) -> NptTime {
+    let nanoseconds = (t.nseconds() % gst::ClockTime::SECOND.nseconds()) as u32;
+    NptTime::Seconds(t.seconds(), (nanoseconds != 0).then_some(nanoseconds))
+}
+
+// Drop-frame timecodes skip the frame numbers 0 and 1 at the start of every minute, except for
+// every tenth minute, to stay in sync with the 29.97 fps frame rate
+const SMPTE_DROP_FRAMES_PER_10_MINUTES: u64 = 17982;
+const SMPTE_DROP_FRAMES_PER_MINUTE: u64 = 1798;
+
+// Actual frame rate of SMPTE timecodes of type `ty`
+fn smpte_frame_rate(ty: &SmpteType) -> Option<(u64, u64)> {
+    match ty {
+        SmpteType::Smpte => Some((30, 1)),
+        SmpteType::Smpte30Drop => Some((30000, 1001)),
+        SmpteType::Smpte25 => Some((25, 1)),
+        SmpteType::Other(_) => None,
+    }
+}
+
+fn smpte_time_to_clock_time(ty: &SmpteType, t: SmpteTime) -> Option<gst::ClockTime> {
+    let (fps_n, fps_d) = smpte_frame_rate(ty)?;
+    // Frames per second as counted by the timecode
+    let fps = fps_n.div_ceil(fps_d);
+    let (frames, subframes) = t
+        .frames
+        .map_or((0, 0), |(f, sf)| (f as u64, sf.unwrap_or(0) as u64));
+
+    let minutes = t.hours as u64 * 60 + t.minutes as u64;
+    let mut frame = (minutes * 60 + t.seconds as u64) * fps + frames;
+    if *ty == SmpteType::Smpte30Drop {
+        frame = frame.checked_sub(2 * (minutes - minutes / 10))?;
+    }
+
+    // Subframes are hundredths of a frame
+    (frame * 100 + subframes)
+        .mul_div_round(fps_d * gst::ClockTime::SECOND.nseconds(), fps_n * 100)
+        .map(gst::ClockTime::from_nseconds)
+}
+
+fn smpte_time_from_clock_time(ty: &SmpteType, t: gst::ClockTime) -> Option<SmpteTime> {
+    let (fps_n, fps_d) = smpte_frame_rate(ty)?;
+    let fps = fps_n.div_ceil(fps_d);
+
+    // Rounded to the nearest subframe
+    let subframes = t
+        .nseconds()
+        .mul_div_round(fps_n * 100, fps_d * gst::ClockTime::SECOND.nseconds())?;
+    let mut frame = subframes / 100;
+    let subframes = (subframes % 100) as u8;
+    if *ty == SmpteType::Smpte30Drop {
+        let rem = frame % SMPTE_DROP_FRAMES_PER_10_MINUTES;
+        frame += 18 * (frame / SMPTE_DROP_FRAMES_PER_10_MINUTES);
+        if rem > 1 {
+            frame += 2 * ((rem - 2) / SMPTE_DROP_FRAMES_PER_MINUTE);
+        }
+    }
+
+    Some(SmpteTime {
+        hours: u8::try_from(frame / (fps * 3600)).ok()?,
+        minutes: ((frame / (fps * 60)) % 60) as u8,
+        seconds: ((frame / fps) % 60) as u8,
+        frames: Some(((frame % fps) as u8, (subframes != 0).then_some(subframes))),
+    })
+}
+
+// Converts an absolute UTC time to nanoseconds since the Unix epoch
+fn utc_time_to_unix(t: UtcTime) -> Option<gst::ClockTime> {
+    let datetime = glib::DateTime::from_utc(
+        (t.date / 10000) as i32,
+        ((t.date / 100) % 100) as i32,
+        (t.date % 100) as i32,
+        (t.time / 10000) as i32,
+        ((t.time / 100) % 100) as i32,
+        (t.time % 100) as f64,
+    )
+    .ok()?;
+    u64::try_from(datetime.to_unix())
+        .ok()?
+        .checked_mul(gst::ClockTime::SECOND.nseconds())?
+        .checked_add(t.nanoseconds.unwrap_or(0) as u64)
+        .map(gst::ClockTime::from_nseconds)
+}
+
+fn utc_time_from_unix(t: gst::ClockTime) -> Option<UtcTime> {
+    let datetime = glib::DateTime::from_unix_utc(i64::try_from(t.seconds()).ok()?).ok()?;
+    let nanoseconds = (t.nseconds() % gst::ClockTime::SECOND.nseconds()) as u32;
+
+    Some(UtcTime {
+        date: u32::try_from(
+            datetime.year() * 10000 + datetime.month() * 100 + datetime.day_of_month(),
+        )
+        .ok()?,
+        time: u32::try_from(datetime.hour() * 10000 + datetime.minute() * 100 + datetime.second())
+            .ok()?,
+        nanoseconds: (nanoseconds != 0).then_some(nanoseconds),
+    })
+}
+
 fn on_rtcp_udp(
     appsink: &gst_app::AppSink,
     tx: mpsc::Sender<MappedBuffer<Readable>>,
@@ -1859,7 +2859,7 @@ fn on_rtcp_udp(
 
 fn on_rtcp_tcp(
     appsink: &gst_app::AppSink,
-    cmd_tx: mpsc::Sender<Commands>,
+    cmd_tx: mpsc::UnboundedSender<Commands>,
     rtcp_channel: u8,
 ) -> Result<gst::FlowSuccess, gst::FlowError> {
     let Ok(sample) = appsink.pull_sample() else {
@@ -1873,8 +2873,9 @@ fn on_rtcp_tcp(
         Ok(map) => {
             let data: rtsp_types::Data<Body> =
                 rtsp_types::Data::new(rtcp_channel, Body::mapped(map));
-            let cmd_tx = cmd_tx.clone();
-            RUNTIME.spawn(async move { cmd_tx.send(Commands::Data(data)).await });
+            if cmd_tx.send(Commands::Data(data)).is_err() {
+                return Err(gst::FlowError::Eos);
+            }
             Ok(gst::FlowSuccess::Ok)
         }
         Err(err) => {
@@ -1890,6 +2891,7 @@ async fn udp_rtp_task(
     timeout: gst::ClockTime,
     receive_mtu: u32,
     sender_addr: Option<SocketAddr>,
+    mut paused: watch::Receiver<bool>,
 ) {
     let t = Duration::from_secs(timeout.into());
     let sender_addr = match sender_addr {
@@ -1897,17 +2899,20 @@ async fn udp_rtp_task(
         // Server didn't give us a Transport header or its Transport header didn't specify the
         // server port, so we don't know the sender port from which we will get data till we get
         // the first packet here.
-        None => {
-            let ret = match time::timeout(t, socket.peek_sender()).await {
-                Ok(Ok(addr)) => Ok(addr),
-                Ok(Err(_elapsed)) => Err(format!(
+        None => loop {
+            let is_paused = *paused.borrow_and_update();
+            let ret = tokio::select! {
+                res = socket.peek_sender() => {
+                    res.map_err(|err| format!("UDP socket was closed: {err:?}"))
+                }
+                _ = time::sleep(t), if !is_paused => Err(format!(
                     "No data after {} seconds, exiting",
                     timeout.seconds()
                 )),
-                Err(err) => Err(format!("UDP socket was closed: {err:?}")),
+                Ok(()) = paused.changed() => continue,
             };
             match ret {
-                Ok(addr) => addr,
+                Ok(addr) => break addr,
                 Err(err) => {
                     gst::element_error!(
                         appsrc,
@@ -1918,7 +2923,7 @@ async fn udp_rtp_task(
                     return;
                 }
             }
-        }
+        },
     };
     gst::info!(CAT, "Receiving from address {sender_addr:?}");
     let gio_addr = {
@@ -1939,7 +2944,15 @@ async fn udp_rtp_task(
         let Ok(mut map) = buffer.into_mapped_buffer_writable() else {
             break "Failed to map buffer writable".to_string();
         };
-        match time::timeout(t, socket.recv_from(map.as_mut_slice())).await {
+        let is_paused = *paused.borrow_and_update();
+        let res = tokio::select! {
+            res = socket.recv_from(map.as_mut_slice()) => Ok(res),
+            // No data is expected while the server has paused the media
+            _ = time::sleep(t), if !is_paused => Err(()),
+            // Re-evaluate the timeout when the media is paused or resumed
+            Ok(()) = paused.changed() => continue,
+        };
+        match res {
             Ok(Ok((len, addr))) => {
                 // Ignore packets from the wrong sender
                 if addr != sender_addr {
@@ -1969,14 +2982,14 @@ async fn udp_rtp_task(
                 bufref.set_dts(t);
                 gst_net::NetAddressMeta::add(bufref, &gio_addr);
                 gst::trace!(CAT, "received RTP packet from {addr:?}");
-                if let Err(err) = appsrc.push_buffer(buffer) {
-                    break format!("UDP buffer push failed: {err:?}");
+                match appsrc.push_buffer(buffer) {
+                    // Data received while flushing for a seek is from before the seek
+                    Ok(_) | Err(gst::FlowError::Flushing) => (),
+                    Err(err) => break format!("UDP buffer push failed: {err:?}"),
                 }
             }
-            Ok(Err(_elapsed)) => {
-                break format!("No data after {} seconds, exiting", timeout.seconds())
-            }
-            Err(err) => break format!("UDP socket was closed: {err:?}"),
+            Ok(Err(err)) => break format!("UDP socket was closed: {err:?}"),
+            Err(()) => break format!("No data after {} seconds, exiting", timeout.seconds()),
         };
     };
     gst::element_error!(
@@ -2061,3 +3074,374 @@ impl ObjectSubclass for RtspSrc {
     type ParentType = gst::Bin;
     type Interfaces = (gst::URIHandler,);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media_range(s: &str) -> MediaRange {
+        MediaRange::from_range(&parse_range(s).unwrap()).unwrap()
+    }
+
+    fn seek_request(flush: bool) -> SeekRequest {
+        SeekRequest {
+            seqnum: gst::Seqnum::next(),
+            flush,
+        }
+    }
+
+    #[test]
+    fn test_playback_seek_while_playing() {
+        gst::init().unwrap();
+
+        for flush in [true, false] {
+            let mut playback = PlaybackState::default();
+            assert_eq!(
+                playback.play(),
+                (Some(parse_range("npt=now-").unwrap()), None)
+            );
+            playback.request_sent(Method::Play, 1, None);
+            assert_eq!(playback.response_received(), Some((Method::Play, 1, None)));
+
+            // PAUSE, then PLAY the new range
+            let range = npt_range(gst::ClockTime::from_seconds(10), None);
+            let seek = seek_request(flush);
+            assert_eq!(playback.seek(range.clone(), seek), Some(range));
+            playback.request_sent(Method::Pause, 2, None);
+            playback.request_sent(Method::Play, 3, Some(seek));
+            assert_eq!(playback.response_received(), Some((Method::Pause, 2, None)));
+            assert_eq!(
+                playback.response_received(),
+                Some((Method::Play, 3, Some(seek)))
+            );
+            assert_eq!(playback.response_received(), None);
+        }
+    }
+
+    #[test]
+    fn test_playback_consecutive_flushing_seeks() {
+        gst::init().unwrap();
+
+        let mut playback = PlaybackState::default();
+        playback.play();
+        playback.request_sent(Method::Play, 1, None);
+        playback.response_received();
+
+        let (seek1, seek2) = (seek_request(true), seek_request(true));
+        for (cseq, seek) in [(2, seek1), (4, seek2)] {
+            let range = npt_range(gst::ClockTime::from_seconds(cseq.into()), None);
+            assert!(playback.seek(range, seek).is_some());
+            playback.request_sent(Method::Pause, cseq, None);
+            playback.request_sent(Method::Play, cseq + 1, Some(seek));
+        }
+
+        // Only the last seek stops flushing
+        playback.response_received();
+        assert_eq!(
+            playback.response_received(),
+            Some((
+                Method::Play,
+                3,
+                Some(SeekRequest {
+                    flush: false,
+                    ..seek1
+                })
+            ))
+        );
+        playback.response_received();
+        assert_eq!(
+            playback.response_received(),
+            Some((Method::Play, 5, Some(seek2)))
+        );
+    }
+
+    #[test]
+    fn test_playback_pause_resume() {
+        let mut playback = PlaybackState::default();
+        // Nothing to pause before the first PLAY
+        assert!(!playback.pause(true));
+        playback.play();
+        playback.request_sent(Method::Play, 1, None);
+        // Live media isn't paused on the server
+        assert!(!playback.pause(false));
+
+        assert!(playback.pause(true));
+        playback.request_sent(Method::Pause, 2, None);
+        assert!(!playback.pause(true));
+
+        // Resume from where the media was paused
+        assert_eq!(playback.play(), (None, None));
+        playback.request_sent(Method::Play, 3, None);
+        assert_eq!(playback.response_received(), Some((Method::Play, 1, None)));
+        assert_eq!(playback.response_received(), Some((Method::Pause, 2, None)));
+        assert_eq!(playback.response_received(), Some((Method::Play, 3, None)));
+    }
+
+    #[test]
+    fn test_playback_seek_while_not_playing() {
+        gst::init().unwrap();
+
+        let mut playback = PlaybackState::default();
+        // Seek before the first PLAY, e.g. in PAUSED after preroll
+        let range = npt_range(gst::ClockTime::from_seconds(10), None);
+        let seek = seek_request(true);
+        assert_eq!(playback.seek(range.clone(), seek), None);
+        assert_eq!(playback.play(), (Some(range), Some(seek)));
+        playback.request_sent(Method::Play, 1, Some(seek));
+        assert_eq!(
+            playback.response_received(),
+            Some((Method::Play, 1, Some(seek)))
+        );
+
+        // Seek while paused, the last seek wins
+        assert!(playback.pause(true));
+        playback.request_sent(Method::Pause, 2, None);
+        playback.response_received();
+        let seek = seek_request(false);
+        assert_eq!(
+            playback.seek(npt_range(gst::ClockTime::ZERO, None), seek),
+            None
+        );
+        let range = npt_range(gst::ClockTime::from_seconds(20), None);
+        let seek = seek_request(true);
+        assert_eq!(playback.seek(range.clone(), seek), None);
+        assert_eq!(playback.play(), (Some(range), Some(seek)));
+    }
+
+    #[test]
+    fn test_seek_to_npt() {
+        let media_info = MediaInfo {
+            start: Some(gst::ClockTime::from_seconds(10)),
+            stop: Some(gst::ClockTime::from_seconds(40)),
+            ..Default::default()
+        };
+        let s = gst::ClockTime::from_seconds;
+        assert_eq!(media_info.duration(), Some(s(30)));
+        assert_eq!(media_info.seek_to_npt(s(5), None), Some((s(15), None)));
+        assert_eq!(
+            media_info.seek_to_npt(s(0), Some(s(50))),
+            Some((s(10), Some(s(40))))
+        );
+        assert_eq!(
+            media_info.seek_to_npt(s(60), Some(s(20))),
+            Some((s(40), Some(s(40))))
+        );
+        assert_eq!(MediaInfo::default().seek_to_npt(s(0), None), None);
+    }
+
+    #[test]
+    fn test_play_segment() {
+        gst::init().unwrap();
+
+        let s = gst::ClockTime::from_seconds;
+        // PLAY response for NPT 25s of media starting at NPT 10s, received at running time 3s
+        let segment = play_segment(Some(s(3)), s(25), s(10));
+        assert_eq!(segment.to_stream_time(s(3)), Some(s(15)));
+        assert_eq!(segment.to_stream_time(s(5)), Some(s(17)));
+        assert_eq!(segment.to_running_time(s(5)), Some(s(5)));
+        // Data received before the start of the range is dropped
+        assert_eq!(segment.to_running_time(s(2)), None);
+    }
+
+    #[test]
+    fn test_udp_buffer_size() {
+        assert_eq!(udp_buffer_size(100_000, Some(8_000)), 100_000);
+        assert_eq!(udp_buffer_size(0, None), 512 * 1024);
+        assert_eq!(udp_buffer_size(0, Some(64)), 512 * 1024);
+        // 2 seconds at 8 Mbit/s
+        assert_eq!(udp_buffer_size(0, Some(8_000)), 2_000_000);
+        assert_eq!(udp_buffer_size(0, Some(u64::MAX)), i32::MAX as usize);
+    }
+
+    #[test]
+    fn test_actual_recv_buffer_size() {
+        if cfg!(target_os = "linux") {
+            assert_eq!(actual_recv_buffer_size(2 * 425_984), 425_984);
+        } else {
+            assert_eq!(actual_recv_buffer_size(425_984), 425_984);
+        }
+
+        // The size reported for a buffer set to what was requested is not capped
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let sock = socket2::SockRef::from(&socket);
+        sock.set_recv_buffer_size(64 * 1024).unwrap();
+        let reported = sock.recv_buffer_size().unwrap();
+        assert!(actual_recv_buffer_size(reported) >= 64 * 1024);
+        assert!(actual_recv_buffer_size(reported) < 2 * 64 * 1024);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_udp_drops() {
+        let _guard = RUNTIME.enter();
+
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.set_nonblocking(true).unwrap();
+        // The kernel rounds this up to its minimum
+        socket2::SockRef::from(&socket)
+            .set_recv_buffer_size(1)
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        let socket = UdpSocket::from_std(socket).unwrap();
+        assert_eq!(udp_drops(&socket).unwrap(), 0);
+
+        let sender = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        for _ in 0..100 {
+            sender.send_to(&[0; 1000], addr).unwrap();
+        }
+        assert!(udp_drops(&socket).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_npt_range() {
+        let range = media_range("npt=now-");
+        assert_eq!(
+            range.range,
+            PlayRange {
+                start: None,
+                stop: None
+            }
+        );
+
+        let range = media_range("npt=0:01:02.5-1:00:00");
+        assert_eq!(
+            range.range,
+            PlayRange {
+                start: Some(gst::ClockTime::from_mseconds(62_500)),
+                stop: Some(gst::ClockTime::from_seconds(3600))
+            }
+        );
+
+        // The `;time=` parameter is ignored
+        let range = media_range("npt=10-20.25;time=19970123T143720Z");
+        assert_eq!(
+            range.range,
+            PlayRange {
+                start: Some(gst::ClockTime::from_seconds(10)),
+                stop: Some(gst::ClockTime::from_mseconds(20_250))
+            }
+        );
+
+        assert_eq!(parse_range("foo=1-2"), None);
+
+        assert_eq!(
+            npt_range(gst::ClockTime::from_mseconds(10_500), None).to_string(),
+            "npt=10.500000000-"
+        );
+        assert_eq!(
+            range
+                .seek_range(
+                    SeekFormat::Npt,
+                    gst::ClockTime::ZERO,
+                    Some(gst::ClockTime::from_seconds(30))
+                )
+                .unwrap()
+                .to_string(),
+            "npt=0-30"
+        );
+    }
+
+    #[test]
+    fn test_smpte_range() {
+        // 1/30th of a second is rounded to the nearest frame
+        let t = smpte_time_from_clock_time(
+            &SmpteType::Smpte,
+            gst::ClockTime::from_nseconds(33_333_333),
+        )
+        .unwrap();
+        assert_eq!(t.to_string(), "00:00:00:01");
+        assert_eq!(
+            smpte_time_to_clock_time(&SmpteType::Smpte, t),
+            Some(gst::ClockTime::from_nseconds(33_333_333))
+        );
+
+        let t =
+            smpte_time_from_clock_time(&SmpteType::Smpte25, gst::ClockTime::from_mseconds(1_050))
+                .unwrap();
+        assert_eq!(t.to_string(), "00:00:01:01.25");
+
+        // Frame 1800 at 29.97 fps is the third frame of the second minute, as frame numbers 0
+        // and 1 are dropped
+        let t = smpte_time_from_clock_time(
+            &SmpteType::Smpte30Drop,
+            gst::ClockTime::from_mseconds(60_060),
+        )
+        .unwrap();
+        assert_eq!(t.to_string(), "00:01:00:02");
+        assert_eq!(
+            smpte_time_to_clock_time(&SmpteType::Smpte30Drop, t),
+            Some(gst::ClockTime::from_mseconds(60_060))
+        );
+        // ...but not of the tenth minute
+        let t: SmpteTime = "00:10:00:00".parse().unwrap();
+        let time = smpte_time_to_clock_time(&SmpteType::Smpte30Drop, t).unwrap();
+        assert_eq!(
+            smpte_time_from_clock_time(&SmpteType::Smpte30Drop, time),
+            Some(t)
+        );
+
+        let range = media_range("smpte-25=00:00:10-00:01:00");
+        assert_eq!(
+            range.range,
+            PlayRange {
+                start: Some(gst::ClockTime::from_seconds(10)),
+                stop: Some(gst::ClockTime::from_seconds(60))
+            }
+        );
+        assert_eq!(
+            range
+                .seek_range(
+                    SeekFormat::Smpte,
+                    gst::ClockTime::from_mseconds(20_040),
+                    None
+                )
+                .unwrap()
+                .to_string(),
+            "smpte-25=00:00:20:01-"
+        );
+    }
+
+    #[test]
+    fn test_clock_range() {
+        let range = media_range("clock=19961108T142300Z-19961108T143520Z");
+        assert_eq!(
+            range.range,
+            PlayRange {
+                start: Some(gst::ClockTime::ZERO),
+                stop: Some(gst::ClockTime::from_seconds(740))
+            }
+        );
+
+        assert_eq!(
+            range
+                .seek_range(
+                    SeekFormat::Clock,
+                    gst::ClockTime::from_mseconds(30_500),
+                    Some(gst::ClockTime::from_seconds(60))
+                )
+                .unwrap()
+                .to_string(),
+            "clock=19961108T142330.500000000Z-19961108T142400Z"
+        );
+
+        // Clock ranges in PLAY responses are relative to the start of the media
+        assert_eq!(
+            range.to_npt(&parse_range("clock=19961108T142330Z-").unwrap()),
+            Some(PlayRange {
+                start: Some(gst::ClockTime::from_seconds(30)),
+                stop: None
+            })
+        );
+
+        // Absolute times are unknown for media described with an NPT range
+        let range = media_range("npt=0-10");
+        assert_eq!(
+            range.seek_range(SeekFormat::Clock, gst::ClockTime::ZERO, None),
+            None
+        );
+        assert_eq!(
+            range.to_npt(&parse_range("clock=19961108T142330Z-").unwrap()),
+            None
+        );
+    }
+}
@@ -21,11 +21,13 @@
  * * RTCP-based A/V sync
  * * Lower transport selection and priority (NEW!)
  *   - Also supports different lower transports for each SETUP
+ * * VOD support: PAUSE and seeking with NPT, SMPTE or clock ranges
+ * * UDP receive buffer sizing based on the stream bitrate, and kernel drops statistics
  *
  * Some missing features:
  * * SET_PARAMETER/GET_PARAMETER messages
  * * SRTP support
- * * VOD support: PAUSE, seeking, etc
+ * * VOD trick modes: non-1.0 playback rates, etc
  * * ONVIF backchannel and trick mode support
  * * and more
  *
@@ -41,11 +43,38 @@ mod sdp;
 mod tcp_message;
 mod transport;
 
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstRtspSrc2SeekFormat")]
+pub enum SeekFormat {
+    #[default]
+    #[enum_value(
+        name = "NPT: Normal Play Time, relative to the start of the media",
+        nick = "npt"
+    )]
+    Npt = 0,
+    #[enum_value(
+        name = "SMPTE: SMPTE timecodes, with the timecode type of the media range or 30 fps",
+        nick = "smpte"
+    )]
+    Smpte = 1,
+    #[enum_value(
+        name = "Clock: Absolute UTC time, only for media described with a clock range",
+        nick = "clock"
+    )]
+    Clock = 2,
+}
+
 glib::wrapper! {
     pub struct RtspSrc(ObjectSubclass<imp::RtspSrc>) @extends gst::Bin, gst::Element, gst::Object, @implements gst::URIHandler;
 }
 
 pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    #[cfg(feature = "doc")]
+    {
+        SeekFormat::static_type().mark_as_plugin_api(gst::PluginAPIFlags::empty());
+    }
+
     gst::Element::register(
         Some(plugin),
         "rtspsrc2",
@@ -103,6 +103,8 @@ struct Settings {
     video_caps: gst::Caps,
     audio_caps: gst::Caps,
     turn_servers: gst::Array,
+    turn_auth_secret: Option<String>,
+    turn_auth_ttl: u32,
     stun_server: Option<String>,
     cc_info: CCInfo,
     do_fec: bool,
@@ -528,6 +530,8 @@ impl Default for Settings {
                 .collect::<gst::Caps>(),
             stun_server: DEFAULT_STUN_SERVER.map(String::from),
             turn_servers: gst::Array::new(Vec::new() as Vec<glib::SendValue>),
+            turn_auth_secret: None,
+            turn_auth_ttl: utils::DEFAULT_TURN_AUTH_TTL,
             cc_info: CCInfo {
                 heuristic: DEFAULT_CONGESTION_CONTROL,
                 min_bitrate: DEFAULT_MIN_BITRATE,
@@ -2872,7 +2876,12 @@ impl BaseWebRTCSink {
             webrtcbin.set_property("stun-server", stun_server);
         }
 
-        for turn_server in settings.turn_servers.iter() {
+        for turn_server in utils::session_turn_servers(
+            &settings.turn_servers,
+            settings.turn_auth_secret.as_deref(),
+            settings.turn_auth_ttl,
+            Some(&session_id),
+        ) {
             webrtcbin.emit_by_name::<bool>("add-turn-server", &[&turn_server]);
         }
 
@@ -4589,6 +4598,18 @@ impl ObjectImpl for BaseWebRTCSink {
                     )
                     .mutable_ready()
                     .build(),
+                glib::ParamSpecString::builder("turn-auth-secret")
+                    .nick("TURN REST API secret")
+                    .blurb("Shared secret of the TURN servers (e.g. coturn static-auth-secret), to generate short-lived credentials for each session instead of using the credentials of turn-servers")
+                    .mutable_ready()
+                    .build(),
+                glib::ParamSpecUInt::builder("turn-auth-ttl")
+                    .nick("TURN credentials TTL")
+                    .blurb("Validity in seconds of the TURN credentials generated with turn-auth-secret, it should cover the duration of the sessions")
+                    .minimum(1)
+                    .default_value(utils::DEFAULT_TURN_AUTH_TTL)
+                    .mutable_ready()
+                    .build(),
                 glib::ParamSpecEnum::builder_with_default("congestion-control", DEFAULT_CONGESTION_CONTROL)
                     .nick("Congestion control")
                     .blurb("Defines how congestion is controlled, if at all")
@@ -4834,6 +4855,16 @@ impl ObjectImpl for BaseWebRTCSink {
                 let mut settings = self.settings.lock().unwrap();
                 settings.turn_servers = value.get::<gst::Array>().expect("type checked upstream")
             }
+            "turn-auth-secret" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.turn_auth_secret = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+            }
+            "turn-auth-ttl" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.turn_auth_ttl = value.get::<u32>().expect("type checked upstream")
+            }
             "congestion-control" => {
                 let mut settings = self.settings.lock().unwrap();
                 settings.cc_info.heuristic = value
@@ -4975,6 +5006,14 @@ impl ObjectImpl for BaseWebRTCSink {
                 let settings = self.settings.lock().unwrap();
                 settings.turn_servers.to_value()
             }
+            "turn-auth-secret" => {
+                let settings = self.settings.lock().unwrap();
+                settings.turn_auth_secret.to_value()
+            }
+            "turn-auth-ttl" => {
+                let settings = self.settings.lock().unwrap();
+                settings.turn_auth_ttl.to_value()
+            }
             "min-bitrate" => {
                 let settings = self.settings.lock().unwrap();
                 settings.cc_info.min_bitrate.to_value()
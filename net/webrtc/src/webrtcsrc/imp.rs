@@ -35,6 +35,8 @@ static CAT: LazyLock<gst::DebugCategory> = LazyLock::new(|| {
 struct Settings {
     stun_server: Option<String>,
     turn_servers: gst::Array,
+    turn_auth_secret: Option<String>,
+    turn_auth_ttl: u32,
     signaller: Signallable,
     meta: Option<gst::Structure>,
     video_codecs: Vec<Codec>,
@@ -89,6 +91,18 @@ impl ObjectImpl for BaseWebRTCSrc {
                    )
                    .mutable_ready()
                    .build(),
+               glib::ParamSpecString::builder("turn-auth-secret")
+                   .nick("TURN REST API secret")
+                   .blurb("Shared secret of the TURN servers (e.g. coturn static-auth-secret), to generate short-lived credentials for each session instead of using the credentials of turn-servers")
+                   .mutable_ready()
+                   .build(),
+               glib::ParamSpecUInt::builder("turn-auth-ttl")
+                   .nick("TURN credentials TTL")
+                   .blurb("Validity in seconds of the TURN credentials generated with turn-auth-secret, it should cover the duration of the sessions")
+                   .minimum(1)
+                   .default_value(utils::DEFAULT_TURN_AUTH_TTL)
+                   .mutable_ready()
+                   .build(),
                glib::ParamSpecObject::builder::<Signallable>("signaller")
                    .flags(glib::ParamFlags::READWRITE | glib::ParamFlags::CONSTRUCT_ONLY)
                    .blurb("The Signallable object to use to handle WebRTC Signalling")
@@ -197,6 +211,15 @@ impl ObjectImpl for BaseWebRTCSrc {
                 let mut settings = self.settings.lock().unwrap();
                 settings.turn_servers = value.get::<gst::Array>().expect("type checked upstream");
             }
+            "turn-auth-secret" => {
+                self.settings.lock().unwrap().turn_auth_secret = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream");
+            }
+            "turn-auth-ttl" => {
+                self.settings.lock().unwrap().turn_auth_ttl =
+                    value.get::<u32>().expect("type checked upstream");
+            }
             "meta" => {
                 self.settings.lock().unwrap().meta = value
                     .get::<Option<gst::Structure>>()
@@ -241,6 +264,8 @@ impl ObjectImpl for BaseWebRTCSrc {
             .to_value(),
             "stun-server" => self.settings.lock().unwrap().stun_server.to_value(),
             "turn-servers" => self.settings.lock().unwrap().turn_servers.to_value(),
+            "turn-auth-secret" => self.settings.lock().unwrap().turn_auth_secret.to_value(),
+            "turn-auth-ttl" => self.settings.lock().unwrap().turn_auth_ttl.to_value(),
             "meta" => self.settings.lock().unwrap().meta.to_value(),
             "enable-data-channel-navigation" => {
                 let settings = self.settings.lock().unwrap();
@@ -307,6 +332,8 @@ impl Default for Settings {
         Self {
             stun_server: DEFAULT_STUN_SERVER.map(|v| v.to_string()),
             turn_servers: Default::default(),
+            turn_auth_secret: None,
+            turn_auth_ttl: utils::DEFAULT_TURN_AUTH_TTL,
             signaller: signaller.upcast(),
             meta: Default::default(),
             audio_codecs: Codecs::audio_codecs()
@@ -1590,7 +1617,12 @@ impl BaseWebRTCSrc {
                 webrtcbin.set_property("stun-server", stun_server);
             }
 
-            for turn_server in settings.turn_servers.iter() {
+            for turn_server in utils::session_turn_servers(
+                &settings.turn_servers,
+                settings.turn_auth_secret.as_deref(),
+                settings.turn_auth_ttl,
+                Some(session_id),
+            ) {
                 webrtcbin.emit_by_name::<bool>("add-turn-server", &[&turn_server]);
             }
         }
@@ -2184,6 +2216,12 @@ pub(super) mod whip {
             element
                 .bind_property("turn-servers", &settings.signaller, "turn-servers")
                 .build();
+            element
+                .bind_property("turn-auth-secret", &settings.signaller, "turn-auth-secret")
+                .build();
+            element
+                .bind_property("turn-auth-ttl", &settings.signaller, "turn-auth-ttl")
+                .build();
         }
     }
 
@@ -2,8 +2,8 @@
 
 use crate::signaller::{Signallable, SignallableExt, SignallableImpl};
 use crate::utils::{
-    build_link_header, build_reqwest_client, parse_redirect_location, set_ice_servers, wait,
-    wait_async, WaitError,
+    build_link_header, build_reqwest_client, parse_redirect_location, session_turn_servers,
+    set_ice_servers, wait, wait_async, WaitError, DEFAULT_TURN_AUTH_TTL,
 };
 use crate::RUNTIME;
 use async_recursion::async_recursion;
@@ -619,6 +619,8 @@ impl ObjectImpl for WhipClient {
 struct WhipServerSettings {
     stun_server: Option<String>,
     turn_servers: gst::Array,
+    turn_auth_secret: Option<String>,
+    turn_auth_ttl: u32,
     host_addr: Url,
     timeout: u32,
     shutdown_signal: Option<tokio::sync::oneshot::Sender<()>>,
@@ -632,6 +634,8 @@ impl Default for WhipServerSettings {
             host_addr: Url::parse(DEFAULT_HOST_ADDR).unwrap(),
             stun_server: DEFAULT_STUN_SERVER.map(String::from),
             turn_servers: gst::Array::new(Vec::new() as Vec<glib::SendValue>),
+            turn_auth_secret: None,
+            turn_auth_ttl: DEFAULT_TURN_AUTH_TTL,
             timeout: DEFAULT_TIMEOUT,
             shutdown_signal: None,
             server_handle: None,
@@ -754,28 +758,24 @@ impl WhipServer {
             None => {}
         }
 
-        if !settings.turn_servers.is_empty() {
-            for turn_server in settings.turn_servers.iter() {
-                if let Ok(turn) = turn_server.get::<String>() {
-                    gst::debug!(CAT, imp = self, "turn server: {}", turn.as_str());
-                    match build_link_header(turn.as_str()) {
-                        Ok(turn_link) => {
-                            links.append(
-                                http::header::LINK,
-                                warp::http::HeaderValue::from_str(turn_link.as_str()).unwrap(),
-                            );
-                        }
-                        Err(e) => {
-                            gst::error!(CAT, imp = self, "Failed to parse {turn_server:?} : {e:?}");
-                        }
-                    }
-                } else {
-                    gst::debug!(
-                        CAT,
-                        imp = self,
-                        "Failed to get String value of {turn_server:?}"
+        // Credentials generated from the TURN REST API secret aren't bound to a session yet
+        for turn in session_turn_servers(
+            &settings.turn_servers,
+            settings.turn_auth_secret.as_deref(),
+            settings.turn_auth_ttl,
+            None,
+        ) {
+            gst::debug!(CAT, imp = self, "turn server: {}", turn.as_str());
+            match build_link_header(turn.as_str()) {
+                Ok(turn_link) => {
+                    links.append(
+                        http::header::LINK,
+                        warp::http::HeaderValue::from_str(turn_link.as_str()).unwrap(),
                     );
                 }
+                Err(e) => {
+                    gst::error!(CAT, imp = self, "Failed to parse {turn:?} : {e:?}");
+                }
             }
         }
 
@@ -886,32 +886,23 @@ impl WhipServer {
             None => {}
         }
 
-        if !settings.turn_servers.is_empty() {
-            for turn_server in settings.turn_servers.iter() {
-                if let Ok(turn) = turn_server.get::<String>() {
-                    gst::debug!(CAT, imp = self, "turn server: {}", turn.as_str());
-                    match build_link_header(turn.as_str()) {
-                        Ok(turn_link) => {
-                            links.append(
-                                http::header::LINK,
-                                http::HeaderValue::from_str(turn_link.as_str()).unwrap(),
-                            );
-                        }
-                        Err(e) => {
-                            gst::error!(
-                                CAT,
-                                imp = self,
-                                "Failed to  parse {turn_server:?} : {e:?}"
-                            );
-                        }
-                    }
-                } else {
-                    gst::error!(
-                        CAT,
-                        imp = self,
-                        "Failed to get String value of {turn_server:?}"
+        for turn in session_turn_servers(
+            &settings.turn_servers,
+            settings.turn_auth_secret.as_deref(),
+            settings.turn_auth_ttl,
+            Some(&session_id),
+        ) {
+            gst::debug!(CAT, imp = self, "turn server: {}", turn.as_str());
+            match build_link_header(turn.as_str()) {
+                Ok(turn_link) => {
+                    links.append(
+                        http::header::LINK,
+                        http::HeaderValue::from_str(turn_link.as_str()).unwrap(),
                     );
                 }
+                Err(e) => {
+                    gst::error!(CAT, imp = self, "Failed to  parse {turn:?} : {e:?}");
+                }
             }
         }
 
@@ -1178,6 +1169,18 @@ impl ObjectImpl for WhipServer {
                     )
                     .mutable_ready()
                     .build(),
+                glib::ParamSpecString::builder("turn-auth-secret")
+                    .nick("TURN REST API secret")
+                    .blurb("Shared secret of the TURN servers (e.g. coturn static-auth-secret), to send short-lived credentials to the WHIP clients instead of the credentials of turn-servers")
+                    .mutable_ready()
+                    .build(),
+                glib::ParamSpecUInt::builder("turn-auth-ttl")
+                    .nick("TURN credentials TTL")
+                    .blurb("Validity in seconds of the TURN credentials generated with turn-auth-secret, it should cover the duration of the sessions")
+                    .minimum(1)
+                    .default_value(DEFAULT_TURN_AUTH_TTL)
+                    .mutable_ready()
+                    .build(),
                 glib::ParamSpecUInt::builder("timeout")
                     .nick("Timeout")
                     .blurb("Value in seconds to timeout WHIP endpoint requests (0 = No timeout).")
@@ -1208,6 +1211,16 @@ impl ObjectImpl for WhipServer {
                 let mut settings = self.settings.lock().unwrap();
                 settings.turn_servers = value.get::<gst::Array>().expect("type checked upstream")
             }
+            "turn-auth-secret" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.turn_auth_secret = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+            }
+            "turn-auth-ttl" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.turn_auth_ttl = value.get().expect("type checked upstream");
+            }
             "timeout" => {
                 let mut settings = self.settings.lock().unwrap();
                 settings.timeout = value.get().unwrap();
@@ -1223,6 +1236,8 @@ impl ObjectImpl for WhipServer {
             "host-addr" => settings.host_addr.to_string().to_value(),
             "stun-server" => settings.stun_server.to_value(),
             "turn-servers" => settings.turn_servers.to_value(),
+            "turn-auth-secret" => settings.turn_auth_secret.to_value(),
+            "turn-auth-ttl" => settings.turn_auth_ttl.to_value(),
             "timeout" => settings.timeout.to_value(),
             _ => unimplemented!(),
         }